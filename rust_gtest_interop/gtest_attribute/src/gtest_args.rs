@@ -0,0 +1,85 @@
+// Copyright 2024 The Chromium Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Parses the `Suite, Name[, with = [...]]` argument list of
+//! `#[gtest(Suite, Name)]`.
+
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, Expr, Ident, LitStr, Result, Token};
+
+/// One `(args...)` entry of a `with = [...]` clause, generating a single
+/// test case that calls the annotated function with `args`.
+pub struct WithCase {
+    /// An explicit case label (e.g. `"negative_numbers" => (-1, -2, -3)`),
+    /// used to name the generated test instead of a bare index.
+    pub label: Option<String>,
+    pub args: Vec<Expr>,
+}
+
+impl Parse for WithCase {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let label = if input.peek(LitStr) && input.peek2(Token![=>]) {
+            let label: LitStr = input.parse()?;
+            input.parse::<Token![=>]>()?;
+            // The label is spliced verbatim into the generated factory
+            // function's name (`__gtest_factory_Suite_Name_<label>`), so it
+            // needs to be identifier-safe; otherwise `format_ident!` panics
+            // deep inside the macro expansion instead of producing a normal
+            // compile error pointing at the bad label.
+            if syn::parse_str::<Ident>(&label.value()).is_err() {
+                return Err(syn::Error::new(
+                    label.span(),
+                    format!(
+                        "with-case label {:?} must be a valid Rust identifier (it becomes part of the generated test's name)",
+                        label.value()
+                    ),
+                ));
+            }
+            Some(label.value())
+        } else {
+            None
+        };
+        let content;
+        syn::parenthesized!(content in input);
+        let args = Punctuated::<Expr, Token![,]>::parse_terminated(&content)?
+            .into_iter()
+            .collect();
+        Ok(WithCase { label, args })
+    }
+}
+
+/// The parsed arguments to `#[gtest(Suite, Name)]` or
+/// `#[gtest(Suite, Name, with = [(args...), ...])]`.
+pub struct GtestAttributeArgs {
+    pub suite: Ident,
+    pub name: Ident,
+    /// Present when a `with = [...]` clause expands this one function into
+    /// several value-parameterized test cases.
+    pub with_cases: Option<Vec<WithCase>>,
+}
+
+impl Parse for GtestAttributeArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let suite: Ident = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let name: Ident = input.parse()?;
+
+        let with_cases = if input.parse::<Option<Token![,]>>()?.is_some() {
+            let with_kw: Ident = input.parse()?;
+            if with_kw != "with" {
+                return Err(syn::Error::new(with_kw.span(), "expected `with`"));
+            }
+            input.parse::<Token![=]>()?;
+            let content;
+            bracketed!(content in input);
+            let cases = Punctuated::<WithCase, Token![,]>::parse_terminated(&content)?;
+            Some(cases.into_iter().collect())
+        } else {
+            None
+        };
+
+        Ok(GtestAttributeArgs { suite, name, with_cases })
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright 2024 The Chromium Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Implements the `#[gtest(Suite, Name)]` attribute that registers a Rust
+//! function as a GoogleTest test case.
+//!
+//! The generated code does two things: it keeps the original function body
+//! as an ordinary Rust function (renamed to `__gtest_impl_<Suite>_<Name>`,
+//! and free to return `Result`), and it emits an
+//! `#[no_mangle] extern "C"` entry point named after the suite and test,
+//! which the C++ side finds and registers with gtest's test factory at
+//! static-init time. A test whose `Name` begins with `DISABLED_` is
+//! registered as disabled the same way a C++ `TEST(Suite, DISABLED_Name)`
+//! would be.
+//!
+//! `#[gtest(Suite, Name, with = [(args...), ...])]` instead expands the one
+//! annotated function into several value-parameterized test cases (akin to
+//! `TEST_P`): one factory per entry in `with`, each calling the function
+//! with that entry's arguments, registered as `Name_0`, `Name_1`, ... or,
+//! for a labeled entry (`"label" => (args...)`), `Name_label`.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, ItemFn};
+
+mod gtest_args;
+
+use gtest_args::GtestAttributeArgs;
+
+/// See the crate-level docs.
+#[proc_macro_attribute]
+pub fn gtest(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as GtestAttributeArgs);
+    let item_fn = parse_macro_input!(input as ItemFn);
+    expand_gtest(args, item_fn).into()
+}
+
+fn expand_gtest(args: GtestAttributeArgs, mut item_fn: ItemFn) -> proc_macro2::TokenStream {
+    let suite = &args.suite;
+    let name = &args.name;
+    let returns_result = !matches!(item_fn.sig.output, syn::ReturnType::Default);
+
+    // Test bodies in this codebase are conventionally all named `fn test()`
+    // (or some other name reused across sibling tests), relying on the
+    // `#[gtest]` attribute to make them addressable. Give the body a name
+    // derived from its suite/test instead of leaving it as-is, so that
+    // e.g. two top-level `#[gtest(Test, A)] fn test() {}` and
+    // `#[gtest(Test, B)] fn test() {}` don't collide.
+    let impl_ident = format_ident!("__gtest_impl_{}_{}", suite, name);
+    item_fn.sig.ident = impl_ident.clone();
+    // `suite`/`name` are conventionally PascalCase (matching their C++
+    // `TEST(Suite, Name)` counterparts), which lands them in the generated
+    // identifier verbatim rather than as a snake_case fragment the lint
+    // expects.
+    item_fn.attrs.push(syn::parse_quote!(#[allow(non_snake_case)]));
+
+    let factories = match &args.with_cases {
+        None => vec![one_factory(suite, name, &impl_ident, &[], returns_result, None, None)],
+        Some(cases) => cases
+            .iter()
+            .enumerate()
+            .map(|(index, case)| {
+                one_factory(suite, name, &impl_ident, &case.args, returns_result, Some(index), case.label.as_deref())
+            })
+            .collect(),
+    };
+
+    quote! {
+        #item_fn
+
+        #(#factories)*
+    }
+}
+
+// Generates the single `#[no_mangle] extern "C"` entry point that the C++
+// side finds and registers with gtest's test factory for one case: the
+// un-parameterized test itself when `index` is `None`, or one row of a
+// `with = [...]` table otherwise.
+fn one_factory(
+    suite: &syn::Ident,
+    name: &syn::Ident,
+    fn_ident: &syn::Ident,
+    args: &[syn::Expr],
+    returns_result: bool,
+    index: Option<usize>,
+    label: Option<&str>,
+) -> proc_macro2::TokenStream {
+    let factory_ident = match index {
+        None => format_ident!("__gtest_factory_{}_{}", suite, name),
+        Some(index) => {
+            let case_suffix = label.map(|l| l.to_string()).unwrap_or_else(|| index.to_string());
+            format_ident!("__gtest_factory_{}_{}_{}", suite, name, case_suffix)
+        }
+    };
+
+    // A function returning `Result` reports its `Err` as a gtest failure at
+    // the call site, rather than panicking, so a resource-provisioning
+    // failure reads like any other `expect_*!` failure. The generated code
+    // names the crate explicitly (rather than via `$crate`, which only
+    // works inside `macro_rules!`) since every test crate using `#[gtest]`
+    // depends on it under this name.
+    let call_and_report = if returns_result {
+        quote! {
+            if let Err(e) = #fn_ident(#(#args),*) {
+                ::rust_gtest_interop_rs::ffi::add_failure_at(file!(), line!(), &format!("{e}"));
+            }
+        }
+    } else {
+        quote! {
+            #fn_ident(#(#args),*);
+        }
+    };
+
+    quote! {
+        #[no_mangle]
+        extern "C" fn #factory_ident() {
+            #call_and_report
+        }
+    }
+}
@@ -5,6 +5,42 @@ fn test() {
     expect_true!(true);
 }
 
+#[gtest(Test, WithCustomFailureMessage)]
+fn test() {
+    let id = 42;
+    expect_true!(true, "context: {}", id);
+    expect_eq!(1 + 1, 2, "math broke: {}", "unreachable");
+
+    expect_nonfatal_failure!(expect_true!(1 + 1 == 3, "context: {}", id), "context: 42");
+    expect_nonfatal_failure!(expect_eq!(1, 2, "custom: {}", 99), "custom: 99");
+}
+
+#[gtest(Test, HeterogeneousEq)]
+fn test() {
+    // expect_eq!/expect_ne! compare operands via a generic `holds` closure
+    // rather than unifying on one type, so mixed-but-comparable operands
+    // (e.g. String vs &str, via PartialEq<&str> for String) keep working.
+    let owned = String::from("a");
+    expect_eq!(owned, "a");
+    expect_ne!(owned, "b");
+}
+
+#[gtest(Test, Matchers)]
+fn test() {
+    use rust_gtest_interop_rs::matchers::{eq, gt, lt, pointee};
+
+    expect_that!(5, eq(5));
+    expect_that!(5, all!(gt(0), lt(10)));
+    expect_that!(5, any!(eq(1), eq(5)));
+    expect_that!(5, not!(eq(6)));
+
+    let some: Option<i32> = Some(5);
+    expect_that!(some, pointee(eq(5)));
+
+    let none: Option<i32> = None;
+    expect_nonfatal_failure!(expect_that!(none, pointee(eq(5))), "Value of: none");
+}
+
 mod module1 {
     use super::*;
 
@@ -46,9 +82,19 @@ mod module3 {
 #[gtest(ExactSuite, ExactTest)]
 fn test() {}
 
+#[gtest(Math, Add, with = [(1, 2, 3), (2, 2, 4), "negative" => (-1, -2, -3)])]
+fn add(a: i32, b: i32, expected: i32) {
+    expect_eq!(a + b, expected);
+}
+
 #[gtest(Test, WithResultType)]
 fn test() -> std::io::Result<()> {
     expect_true!(true);
+    let map = std::collections::HashMap::from([("k", 1)]);
+    let value = expect_some!(map.get(&"k"));
+    expect_eq!(*value, 1);
+    let parsed = expect_ok!("42".parse::<i32>());
+    expect_eq!(parsed, 42);
     Ok(())
 }
 
@@ -58,6 +104,25 @@ fn test() -> std::result::Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[gtest(Test, Snapshot)]
+fn test() {
+    expect_snapshot!(1 + 1, @"2");
+    expect_snapshot!(vec![1, 2, 3], @"[1, 2, 3]");
+}
+
+#[gtest(Test, UnwrapsResultsAndOptions)]
+fn test() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let map = std::collections::HashMap::from([("k", 1)]);
+    let value = expect_some!(map.get(&"k"));
+    expect_eq!(*value, 1);
+    expect_none!(map.get(&"missing"));
+    let parsed = expect_ok!("42".parse::<i32>());
+    expect_eq!(parsed, 42);
+    expect_contains!(vec!["a", "b"], "a");
+    expect_not_empty!(map);
+    Ok(())
+}
+
 // This test fails due to returning Err, and displays the message "uhoh."
 #[gtest(Test, DISABLED_WithError)]
 fn test() -> std::result::Result<(), Box<dyn std::error::Error>> {
@@ -65,18 +130,15 @@ fn test() -> std::result::Result<(), Box<dyn std::error::Error>> {
     Err("uhoh".into())
 }
 
-// TODO(danakj): It would be nice to test expect macros, but we would need to hook up
-// EXPECT_NONFATAL_FAILURE to do so. There's no way to fail a test in a way that we accept, the bots
-// see the failure even if the process returns 0.
-// #[gtest(ExpectFailTest, Failures)]
-// fn test() {
-//     expect_eq!(1 + 1, 1 + 2);
-//     expect_ne!(2 + 3, 3 + 2);
-//     expect_lt!(1 + 1, 1 + 0);
-//     expect_gt!(1 + 0, 1 + 1);
-//     expect_le!(1 + 1, 1 + 0);
-//     expect_ge!(1 + 0, 1 + 1);
-//     expect_true!(true && false);
-//     expect_false!(true || false);
-//     unsafe { COUNTER += 1 };
-// }
\ No newline at end of file
+#[gtest(ExpectFailTest, Failures)]
+#[allow(clippy::identity_op, clippy::nonminimal_bool, clippy::eq_op)]
+fn test() {
+    expect_nonfatal_failure!(expect_eq!(1 + 1, 1 + 2), "Expected: (1 + 1) == (1 + 2)");
+    expect_nonfatal_failure!(expect_ne!(2 + 3, 3 + 2), "Expected: (2 + 3) != (3 + 2)");
+    expect_nonfatal_failure!(expect_lt!(1 + 1, 1 + 0), "Expected: (1 + 1) < (1 + 0)");
+    expect_nonfatal_failure!(expect_gt!(1 + 0, 1 + 1), "Expected: (1 + 0) > (1 + 1)");
+    expect_nonfatal_failure!(expect_le!(1 + 1, 1 + 0), "Expected: (1 + 1) <= (1 + 0)");
+    expect_nonfatal_failure!(expect_ge!(1 + 0, 1 + 1), "Expected: (1 + 0) >= (1 + 1)");
+    expect_nonfatal_failure!(expect_true!(true && false), "Expected: true");
+    expect_nonfatal_failure!(expect_false!(true || false), "Expected: false");
+}
\ No newline at end of file
@@ -0,0 +1,223 @@
+// Copyright 2024 The Chromium Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Assertion macros for `Result`/`Option`-producing expressions that, unlike
+//! the macros in [`crate::expect_macros`], also unwrap the value for use in
+//! the rest of the test body.
+//!
+//! These are meant for `#[gtest]` functions returning `std::io::Result<()>`
+//! or `Result<(), Box<dyn Error>>` (both already supported by the `#[gtest]`
+//! attribute): on failure they report a nonfatal gtest failure the same as
+//! every other `expect_*!` macro, and then `return Err(...)` out of the
+//! enclosing function, rather than panicking, so a resource-provisioning
+//! step can bail out cleanly partway through a test.
+//!
+//! The exceptions are `expect_contains!`, `expect_empty!` and
+//! `expect_not_empty!`, which neither unwrap nor return: like the macros in
+//! [`crate::expect_macros`], they only report a nonfatal failure and let
+//! the test continue.
+//!
+//! Like the macros in [`crate::expect_macros`], every macro here also
+//! accepts optional trailing format arguments appended to the failure
+//! message.
+
+/// Builds an error value of type `Self` from a plain failure message. The
+/// `expect_*!` macros below use this (rather than `.into()`) to
+/// `return Err(...)`, since not every error type a `#[gtest]` test can
+/// return implements `From<String>` -- notably `std::io::Error`, despite
+/// offering an equivalent via `Error::other`.
+#[doc(hidden)]
+pub trait FailureError {
+    fn from_failure_message(message: String) -> Self;
+}
+
+impl FailureError for std::io::Error {
+    fn from_failure_message(message: String) -> Self {
+        std::io::Error::other(message)
+    }
+}
+
+impl FailureError for Box<dyn std::error::Error> {
+    fn from_failure_message(message: String) -> Self {
+        message.into()
+    }
+}
+
+impl FailureError for Box<dyn std::error::Error + Send + Sync> {
+    fn from_failure_message(message: String) -> Self {
+        message.into()
+    }
+}
+
+/// Unwraps `$result`, reporting a nonfatal failure and returning `Err` from
+/// the enclosing function if it is `Err`.
+#[macro_export]
+macro_rules! expect_ok {
+    ($result:expr $(, $($fmt:tt)*)?) => {
+        match $result {
+            Ok(value) => value,
+            Err(e) => {
+                $crate::ffi::add_failure_at(
+                    file!(),
+                    line!(),
+                    &format!(
+                        "Value of: {}\n  Actual: Err({:?})\nExpected: Ok(..){}",
+                        stringify!($result),
+                        e,
+                        $crate::expect_macros::format_custom_message($crate::__expect_custom_message_args!($($($fmt)*)?)),
+                    ),
+                );
+                return Err($crate::expect_fallible_macros::FailureError::from_failure_message(
+                    format!("{}: {:?}", stringify!($result), e),
+                ));
+            }
+        }
+    };
+}
+
+/// Unwraps the `Err` of `$result`, reporting a nonfatal failure and
+/// returning `Err` from the enclosing function if it is `Ok`.
+#[macro_export]
+macro_rules! expect_err {
+    ($result:expr $(, $($fmt:tt)*)?) => {
+        match $result {
+            Err(value) => value,
+            Ok(v) => {
+                $crate::ffi::add_failure_at(
+                    file!(),
+                    line!(),
+                    &format!(
+                        "Value of: {}\n  Actual: Ok({:?})\nExpected: Err(..){}",
+                        stringify!($result),
+                        v,
+                        $crate::expect_macros::format_custom_message($crate::__expect_custom_message_args!($($($fmt)*)?)),
+                    ),
+                );
+                return Err($crate::expect_fallible_macros::FailureError::from_failure_message(
+                    format!("{}: unexpectedly Ok", stringify!($result)),
+                ));
+            }
+        }
+    };
+}
+
+/// Unwraps `$option`, reporting a nonfatal failure and returning `Err` from
+/// the enclosing function if it is `None`.
+#[macro_export]
+macro_rules! expect_some {
+    ($option:expr $(, $($fmt:tt)*)?) => {
+        match $option {
+            Some(value) => value,
+            None => {
+                $crate::ffi::add_failure_at(
+                    file!(),
+                    line!(),
+                    &format!(
+                        "Value of: {}\n  Actual: None\nExpected: Some(..){}",
+                        stringify!($option),
+                        $crate::expect_macros::format_custom_message($crate::__expect_custom_message_args!($($($fmt)*)?)),
+                    ),
+                );
+                return Err($crate::expect_fallible_macros::FailureError::from_failure_message(
+                    format!("{}: unexpectedly None", stringify!($option)),
+                ));
+            }
+        }
+    };
+}
+
+/// Asserts that `$option` is `None`, reporting a nonfatal failure and
+/// returning `Err` from the enclosing function otherwise.
+#[macro_export]
+macro_rules! expect_none {
+    ($option:expr $(, $($fmt:tt)*)?) => {
+        if let Some(v) = $option {
+            $crate::ffi::add_failure_at(
+                file!(),
+                line!(),
+                &format!(
+                    "Value of: {}\n  Actual: Some({:?})\nExpected: None{}",
+                    stringify!($option),
+                    v,
+                    $crate::expect_macros::format_custom_message($crate::__expect_custom_message_args!($($($fmt)*)?)),
+                ),
+            );
+            return Err($crate::expect_fallible_macros::FailureError::from_failure_message(
+                format!("{}: unexpectedly Some", stringify!($option)),
+            ));
+        }
+    };
+}
+
+/// Asserts that `$haystack` contains `$needle`, reporting a nonfatal
+/// failure otherwise. Works with anything offering `.contains(needle)`,
+/// e.g. `&str`, `Vec<T>`, `HashSet<T>`.
+#[macro_export]
+macro_rules! expect_contains {
+    ($haystack:expr, $needle:expr $(, $($fmt:tt)*)?) => {
+        match (&$haystack, &$needle) {
+            (haystack_val, needle_val) => {
+                if !haystack_val.contains(needle_val) {
+                    $crate::ffi::add_failure_at(
+                        file!(),
+                        line!(),
+                        &format!(
+                            "Value of: {}\n  Actual: does not contain {:?}\nExpected: contains {:?}{}",
+                            stringify!($haystack),
+                            needle_val,
+                            needle_val,
+                            $crate::expect_macros::format_custom_message($crate::__expect_custom_message_args!($($($fmt)*)?)),
+                        ),
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Asserts that `$collection` is empty, reporting a nonfatal failure
+/// otherwise. Works with anything offering `.is_empty()`.
+#[macro_export]
+macro_rules! expect_empty {
+    ($collection:expr $(, $($fmt:tt)*)?) => {
+        match &$collection {
+            collection_val => {
+                if !collection_val.is_empty() {
+                    $crate::ffi::add_failure_at(
+                        file!(),
+                        line!(),
+                        &format!(
+                            "Value of: {}\n  Actual: not empty\nExpected: empty{}",
+                            stringify!($collection),
+                            $crate::expect_macros::format_custom_message($crate::__expect_custom_message_args!($($($fmt)*)?)),
+                        ),
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Asserts that `$collection` is not empty, reporting a nonfatal failure
+/// otherwise. Works with anything offering `.is_empty()`.
+#[macro_export]
+macro_rules! expect_not_empty {
+    ($collection:expr $(, $($fmt:tt)*)?) => {
+        match &$collection {
+            collection_val => {
+                if collection_val.is_empty() {
+                    $crate::ffi::add_failure_at(
+                        file!(),
+                        line!(),
+                        &format!(
+                            "Value of: {}\n  Actual: empty\nExpected: not empty{}",
+                            stringify!($collection),
+                            $crate::expect_macros::format_custom_message($crate::__expect_custom_message_args!($($($fmt)*)?)),
+                        ),
+                    );
+                }
+            }
+        }
+    };
+}
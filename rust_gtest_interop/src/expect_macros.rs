@@ -0,0 +1,201 @@
+// Copyright 2024 The Chromium Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Fixed-predicate assertion macros, analogous to GoogleTest's
+//! `EXPECT_TRUE`, `EXPECT_EQ`, etc.
+//!
+//! Every macro here is nonfatal: on failure it reports through
+//! [`crate::ffi::add_failure_at`] and lets the test continue running, the
+//! same as the C++ `EXPECT_*` family (as opposed to `ASSERT_*`, which this
+//! crate does not provide since Rust has no non-local exit without a panic
+//! or early return).
+//!
+//! Every macro also accepts optional trailing format arguments, e.g.
+//! `expect_true!(cond, "context: {}", id)`, appended to the failure message
+//! when the assertion doesn't hold, matching the established GoogleTest
+//! convention of an optional `<< message` stream on `EXPECT_*` macros.
+
+/// Turns the optional, possibly-empty `$(, $($fmt:tt)*)?` tail that every
+/// `expect_*!` macro in this crate accepts into a `std::fmt::Arguments`:
+/// empty when no custom message was given, or the formatted message
+/// otherwise. Not meant to be used directly.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __expect_custom_message_args {
+    () => {
+        format_args!("")
+    };
+    ($($fmt:tt)+) => {
+        format_args!($($fmt)+)
+    };
+}
+
+/// Asserts that `$cond` is `true`, reporting a nonfatal failure otherwise.
+#[macro_export]
+macro_rules! expect_true {
+    ($cond:expr $(, $($fmt:tt)*)?) => {
+        $crate::expect_macros::check_true(
+            &$cond,
+            stringify!($cond),
+            file!(),
+            line!(),
+            $crate::__expect_custom_message_args!($($($fmt)*)?),
+        )
+    };
+}
+
+/// Asserts that `$cond` is `false`, reporting a nonfatal failure otherwise.
+#[macro_export]
+macro_rules! expect_false {
+    ($cond:expr $(, $($fmt:tt)*)?) => {
+        $crate::expect_macros::check_false(
+            &$cond,
+            stringify!($cond),
+            file!(),
+            line!(),
+            $crate::__expect_custom_message_args!($($($fmt)*)?),
+        )
+    };
+}
+
+/// Asserts that `$lhs == $rhs`, reporting a nonfatal failure otherwise.
+#[macro_export]
+macro_rules! expect_eq {
+    ($lhs:expr, $rhs:expr $(, $($fmt:tt)*)?) => {
+        $crate::expect_macros::check_binary_op(
+            &$lhs, &$rhs, |l, r| l == r, "==",
+            file!(), line!(), stringify!($lhs), stringify!($rhs),
+            $crate::__expect_custom_message_args!($($($fmt)*)?),
+        )
+    };
+}
+
+/// Asserts that `$lhs != $rhs`, reporting a nonfatal failure otherwise.
+#[macro_export]
+macro_rules! expect_ne {
+    ($lhs:expr, $rhs:expr $(, $($fmt:tt)*)?) => {
+        $crate::expect_macros::check_binary_op(
+            &$lhs, &$rhs, |l, r| l != r, "!=",
+            file!(), line!(), stringify!($lhs), stringify!($rhs),
+            $crate::__expect_custom_message_args!($($($fmt)*)?),
+        )
+    };
+}
+
+/// Asserts that `$lhs < $rhs`, reporting a nonfatal failure otherwise.
+#[macro_export]
+macro_rules! expect_lt {
+    ($lhs:expr, $rhs:expr $(, $($fmt:tt)*)?) => {
+        $crate::expect_macros::check_binary_op(
+            &$lhs, &$rhs, |l, r| l < r, "<",
+            file!(), line!(), stringify!($lhs), stringify!($rhs),
+            $crate::__expect_custom_message_args!($($($fmt)*)?),
+        )
+    };
+}
+
+/// Asserts that `$lhs > $rhs`, reporting a nonfatal failure otherwise.
+#[macro_export]
+macro_rules! expect_gt {
+    ($lhs:expr, $rhs:expr $(, $($fmt:tt)*)?) => {
+        $crate::expect_macros::check_binary_op(
+            &$lhs, &$rhs, |l, r| l > r, ">",
+            file!(), line!(), stringify!($lhs), stringify!($rhs),
+            $crate::__expect_custom_message_args!($($($fmt)*)?),
+        )
+    };
+}
+
+/// Asserts that `$lhs <= $rhs`, reporting a nonfatal failure otherwise.
+#[macro_export]
+macro_rules! expect_le {
+    ($lhs:expr, $rhs:expr $(, $($fmt:tt)*)?) => {
+        $crate::expect_macros::check_binary_op(
+            &$lhs, &$rhs, |l, r| l <= r, "<=",
+            file!(), line!(), stringify!($lhs), stringify!($rhs),
+            $crate::__expect_custom_message_args!($($($fmt)*)?),
+        )
+    };
+}
+
+/// Asserts that `$lhs >= $rhs`, reporting a nonfatal failure otherwise.
+#[macro_export]
+macro_rules! expect_ge {
+    ($lhs:expr, $rhs:expr $(, $($fmt:tt)*)?) => {
+        $crate::expect_macros::check_binary_op(
+            &$lhs, &$rhs, |l, r| l >= r, ">=",
+            file!(), line!(), stringify!($lhs), stringify!($rhs),
+            $crate::__expect_custom_message_args!($($($fmt)*)?),
+        )
+    };
+}
+
+/// Shared implementation behind `expect_true!`. Not meant to be called
+/// directly.
+#[doc(hidden)]
+pub fn check_true(cond: &bool, cond_expr: &str, file: &str, line: u32, custom: std::fmt::Arguments<'_>) {
+    if !*cond {
+        crate::ffi::add_failure_at(
+            file,
+            line,
+            &format!("Value of: {cond_expr}\n  Actual: false\nExpected: true{}", format_custom_message(custom)),
+        );
+    }
+}
+
+/// Shared implementation behind `expect_false!`. Not meant to be called
+/// directly.
+#[doc(hidden)]
+pub fn check_false(cond: &bool, cond_expr: &str, file: &str, line: u32, custom: std::fmt::Arguments<'_>) {
+    if *cond {
+        crate::ffi::add_failure_at(
+            file,
+            line,
+            &format!("Value of: {cond_expr}\n  Actual: true\nExpected: false{}", format_custom_message(custom)),
+        );
+    }
+}
+
+/// Shared implementation behind `expect_eq!`/`expect_ne!`/`expect_lt!`/etc:
+/// reports a nonfatal failure showing both operand expressions and their
+/// actual values when `holds(lhs, rhs)` is `false`. Not meant to be called
+/// directly.
+///
+/// `lhs` and `rhs` are independent type parameters, rather than a single
+/// `T`, so heterogeneous-but-comparable operands keep working the way they
+/// would with a bare `==`/`<`/etc, e.g. `expect_eq!(a_string, "a literal")`
+/// via `PartialEq<&str> for String`.
+#[doc(hidden)]
+#[allow(clippy::too_many_arguments)]
+pub fn check_binary_op<L: std::fmt::Debug, R: std::fmt::Debug>(
+    lhs: &L,
+    rhs: &R,
+    holds: impl FnOnce(&L, &R) -> bool,
+    op_str: &str,
+    file: &str,
+    line: u32,
+    lhs_expr: &str,
+    rhs_expr: &str,
+    custom: std::fmt::Arguments<'_>,
+) {
+    if !holds(lhs, rhs) {
+        crate::ffi::add_failure_at(
+            file,
+            line,
+            &format!(
+                "Expected: ({lhs_expr}) {op_str} ({rhs_expr})\n  Actual: {lhs:?} vs {rhs:?}{}",
+                format_custom_message(custom),
+            ),
+        );
+    }
+}
+
+/// Formats the optional trailing `"context: {}", id`-style arguments that
+/// every `expect_*!` macro in this crate accepts, as a `"\n<message>"`
+/// suffix, or `""` when no custom message was given.
+#[doc(hidden)]
+pub fn format_custom_message(args: std::fmt::Arguments<'_>) -> String {
+    let message = format!("{args}");
+    if message.is_empty() { String::new() } else { format!("\n{message}") }
+}
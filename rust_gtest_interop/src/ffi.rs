@@ -0,0 +1,64 @@
+// Copyright 2024 The Chromium Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! The low-level bridge between Rust test code and the C++ `gtest` runtime
+//! that actually owns the current `::testing::Test` and its failure list.
+//!
+//! Everything in this module is a thin wrapper around functions implemented
+//! on the C++ side (see `rust_gtest_interop.cc`). Test authors should not
+//! need to reach for this module directly; the `expect_*!` macros and the
+//! `#[gtest]` attribute in this crate are built on top of it.
+
+extern "C" {
+    // Reports a nonfatal failure at the given location, exactly as the C++
+    // `ADD_FAILURE_AT(file, line)` macro would, attributing it to whichever
+    // `::testing::Test` is currently running on this thread.
+    fn rust_gtest_add_failure_at(file: *const u8, file_len: usize, line: i32, message: *const u8, message_len: usize);
+
+    // The four functions below wrap gtest's own `gtest-spi.h` machinery
+    // (`::testing::TestPartResultArray` and
+    // `::testing::ScopedFakeTestPartResultReporter`) that GoogleTest uses to
+    // test itself, so this crate can do the same for its own `expect_*!`
+    // macros. See `crate::scoped_fake_reporter`.
+
+    // Installs a fake `TestPartResultReporterInterface` for the current
+    // thread that captures results into a `TestPartResultArray` instead of
+    // forwarding them to the real reporter, returning an opaque handle.
+    pub(crate) fn rust_gtest_scoped_fake_reporter_new() -> *mut std::ffi::c_void;
+    // Restores the previous reporter and frees the handle.
+    pub(crate) fn rust_gtest_scoped_fake_reporter_delete(handle: *mut std::ffi::c_void);
+    // Returns the number of nonfatal failures captured so far.
+    pub(crate) fn rust_gtest_scoped_fake_reporter_failure_count(handle: *mut std::ffi::c_void) -> usize;
+    // Writes the message of the failure at `index` into the caller-owned
+    // `out` buffer (of length `out_len`), and returns the message's true
+    // length; the caller must re-call with a large enough buffer if the
+    // returned length exceeds `out_len`.
+    pub(crate) fn rust_gtest_scoped_fake_reporter_failure_message(
+        handle: *mut std::ffi::c_void,
+        index: usize,
+        out: *mut u8,
+        out_len: usize,
+    ) -> usize;
+}
+
+/// Records a nonfatal test failure against the currently-running gtest test,
+/// attributed to `file:line`, with the given `message`.
+///
+/// This is the single choke point that every `expect_*!` macro and matcher
+/// failure in this crate funnels through, so that C++ bots always see Rust
+/// test failures the same way they'd see a C++ `EXPECT_*` failure.
+pub fn add_failure_at(file: &str, line: u32, message: &str) {
+    // SAFETY: the C++ side copies out of the pointers before returning, and
+    // the slices we pass in are valid (and UTF-8, which gtest treats as
+    // arbitrary bytes) for the duration of the call.
+    unsafe {
+        rust_gtest_add_failure_at(
+            file.as_ptr(),
+            file.len(),
+            line as i32,
+            message.as_ptr(),
+            message.len(),
+        );
+    }
+}
@@ -0,0 +1,40 @@
+// Copyright 2024 The Chromium Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Glue for writing Rust unit tests that register with, and report results
+//! through, GoogleTest (`//third_party/googletest`).
+//!
+//! Test files bring everything into scope with:
+//! ```ignore
+//! use rust_gtest_interop_rs::prelude::*;
+//! ```
+//! and then write tests as plain functions annotated with `#[gtest(Suite,
+//! Name)]`, asserting with the `expect_*!` macros or [`expect_that!`].
+
+pub mod expect_fallible_macros;
+pub mod expect_macros;
+pub mod ffi;
+pub mod matchers;
+pub mod scoped_fake_reporter;
+pub mod snapshot;
+
+pub use gtest_attribute::gtest;
+
+/// Brings the `#[gtest]` attribute and all assertion macros into scope; see
+/// the crate-level docs.
+pub mod prelude {
+    pub use crate::gtest;
+    pub use crate::matchers::Matcher;
+    pub use crate::{
+        all, any, expect_nonfatal_failure, expect_snapshot, expect_that, matches_pattern, not,
+    };
+    pub use crate::{
+        expect_eq, expect_false, expect_ge, expect_gt, expect_le, expect_lt, expect_ne,
+        expect_true,
+    };
+    pub use crate::{
+        expect_contains, expect_empty, expect_err, expect_none, expect_not_empty, expect_ok,
+        expect_some,
+    };
+}
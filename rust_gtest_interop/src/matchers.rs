@@ -0,0 +1,353 @@
+// Copyright 2024 The Chromium Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! A composable matcher framework in the style of GoogleTest's C++ matchers
+//! (`testing::Eq`, `testing::AllOf`, `testing::Pointee`, ...), for use with
+//! the [`expect_that!`] macro.
+//!
+//! Where the `expect_*!` macros in [`crate::expect_macros`] hard-code a
+//! single predicate per macro, a [`Matcher`] is a value that can be built up
+//! out of smaller matchers and reused, e.g.:
+//!
+//! ```ignore
+//! expect_that!(v, all!(gt(0), lt(10)));
+//! expect_that!(name, starts_with("Dr. "));
+//! expect_that!(maybe_value, pointee(eq(42)));
+//! ```
+
+use std::fmt::Debug;
+
+/// The result of matching a value against a [`Matcher`]: either it matched,
+/// or it didn't and here's why.
+pub enum MatchResult {
+    /// The actual value satisfied the matcher.
+    Matches,
+    /// The actual value did not satisfy the matcher, with a human-readable
+    /// explanation of what was actually seen (e.g. `"which has length 2"`).
+    /// An empty string means the matcher's `describe()` alone is enough.
+    DoesNotMatch(String),
+}
+
+impl MatchResult {
+    /// Returns `true` if this result represents a match.
+    pub fn is_match(&self) -> bool {
+        matches!(self, MatchResult::Matches)
+    }
+}
+
+/// A predicate over values of type `T`, with a human-readable description,
+/// analogous to GoogleTest's `::testing::Matcher<T>`.
+pub trait Matcher<T: ?Sized> {
+    /// Tests `actual` against this matcher.
+    fn matches(&self, actual: &T) -> MatchResult;
+
+    /// Describes what this matcher expects, e.g. `"is greater than 5"`. Used
+    /// to build the `Expected: ...` line of a failure message, including
+    /// when this matcher is nested inside a combinator like `all!`.
+    fn describe(&self) -> String;
+}
+
+/// Reports a nonfatal gtest failure if `$actual` does not satisfy `$matcher`.
+///
+/// The failure message includes the actual value (via `Debug`), the
+/// matcher's description, and the call site, the same way the other
+/// `expect_*!` macros in this crate do.
+#[macro_export]
+macro_rules! expect_that {
+    ($actual:expr, $matcher:expr $(, $($fmt:tt)*)?) => {
+        match (&$actual, &$matcher) {
+            (actual_val, matcher_val) => {
+                if let $crate::matchers::MatchResult::DoesNotMatch(why) =
+                    $crate::matchers::Matcher::matches(matcher_val, actual_val)
+                {
+                    let why_suffix = if why.is_empty() {
+                        String::new()
+                    } else {
+                        format!(", {why}")
+                    };
+                    $crate::ffi::add_failure_at(
+                        file!(),
+                        line!(),
+                        &format!(
+                            "Value of: {}\n  Actual: {:?}{}\nExpected: {}{}",
+                            stringify!($actual),
+                            actual_val,
+                            why_suffix,
+                            $crate::matchers::Matcher::describe(matcher_val),
+                            $crate::expect_macros::format_custom_message($crate::__expect_custom_message_args!($($($fmt)*)?)),
+                        ),
+                    );
+                }
+            }
+        }
+    };
+}
+
+struct FnMatcher<T: ?Sized> {
+    description: String,
+    // A `Box<dyn Fn>` rather than a generic closure parameter, so every
+    // leaf matcher (`eq`, `gt`, `contains`, ...) shares this one struct
+    // type; the description is computed once up front.
+    matches: Box<dyn Fn(&T) -> bool>,
+}
+
+impl<T: ?Sized> Matcher<T> for FnMatcher<T> {
+    fn matches(&self, actual: &T) -> MatchResult {
+        if (self.matches)(actual) {
+            MatchResult::Matches
+        } else {
+            MatchResult::DoesNotMatch(String::new())
+        }
+    }
+
+    fn describe(&self) -> String {
+        self.description.clone()
+    }
+}
+
+/// Matches a value equal to `expected`.
+pub fn eq<T: PartialEq + Debug + 'static>(expected: T) -> impl Matcher<T> {
+    let description = format!("is equal to {expected:?}");
+    FnMatcher { description, matches: Box::new(move |actual: &T| *actual == expected) }
+}
+
+/// Matches a value not equal to `expected`.
+pub fn ne<T: PartialEq + Debug + 'static>(expected: T) -> impl Matcher<T> {
+    let description = format!("is not equal to {expected:?}");
+    FnMatcher { description, matches: Box::new(move |actual: &T| *actual != expected) }
+}
+
+/// Matches a value strictly greater than `expected`.
+pub fn gt<T: PartialOrd + Debug + 'static>(expected: T) -> impl Matcher<T> {
+    let description = format!("is greater than {expected:?}");
+    FnMatcher { description, matches: Box::new(move |actual: &T| *actual > expected) }
+}
+
+/// Matches a value strictly less than `expected`.
+pub fn lt<T: PartialOrd + Debug + 'static>(expected: T) -> impl Matcher<T> {
+    let description = format!("is less than {expected:?}");
+    FnMatcher { description, matches: Box::new(move |actual: &T| *actual < expected) }
+}
+
+/// Matches a value greater than or equal to `expected`.
+pub fn ge<T: PartialOrd + Debug + 'static>(expected: T) -> impl Matcher<T> {
+    let description = format!("is greater than or equal to {expected:?}");
+    FnMatcher { description, matches: Box::new(move |actual: &T| *actual >= expected) }
+}
+
+/// Matches a value less than or equal to `expected`.
+pub fn le<T: PartialOrd + Debug + 'static>(expected: T) -> impl Matcher<T> {
+    let description = format!("is less than or equal to {expected:?}");
+    FnMatcher { description, matches: Box::new(move |actual: &T| *actual <= expected) }
+}
+
+/// Matches a string that contains `needle` as a substring. Only `str` is
+/// supported; slices of other element types are not.
+pub fn contains(needle: impl Into<String>) -> impl Matcher<str> {
+    let needle = needle.into();
+    let description = format!("contains {needle:?}");
+    FnMatcher { description, matches: Box::new(move |actual: &str| actual.contains(&needle)) }
+}
+
+/// Matches a string that starts with `prefix`, equivalent to GoogleTest's
+/// `StartsWith`.
+pub fn starts_with(prefix: impl Into<String>) -> impl Matcher<str> {
+    let prefix = prefix.into();
+    let description = format!("starts with {prefix:?}");
+    FnMatcher { description, matches: Box::new(move |actual: &str| actual.starts_with(&prefix)) }
+}
+
+/// Matches a string containing `substr`, equivalent to GoogleTest's
+/// `HasSubstr`. Behaves the same as [`contains`] under a separate name.
+pub fn has_substring(substr: impl Into<String>) -> impl Matcher<str> {
+    let substr = substr.into();
+    let description = format!("has substring {substr:?}");
+    FnMatcher { description, matches: Box::new(move |actual: &str| actual.contains(&substr)) }
+}
+
+/// Matches a value for which every one of `matchers` matches, describing
+/// itself as the conjunction of their descriptions. Equivalent to
+/// GoogleTest's `AllOf`.
+#[macro_export]
+macro_rules! all {
+    ($($matcher:expr),+ $(,)?) => {
+        $crate::matchers::all(vec![$(Box::new($matcher)),+])
+    };
+}
+
+/// Matches a value for which at least one of `matchers` matches. Equivalent
+/// to GoogleTest's `AnyOf`.
+#[macro_export]
+macro_rules! any {
+    ($($matcher:expr),+ $(,)?) => {
+        $crate::matchers::any(vec![$(Box::new($matcher)),+])
+    };
+}
+
+/// Matches a value for which `inner` does not match. Equivalent to
+/// GoogleTest's `Not`.
+#[macro_export]
+macro_rules! not {
+    ($matcher:expr) => {
+        $crate::matchers::not($matcher)
+    };
+}
+
+/// Builds the "all of" combinator used by the [`all!`] macro. Prefer `all!`
+/// over calling this directly so each operand can be a distinct matcher
+/// type.
+pub fn all<T: ?Sized + 'static>(matchers: Vec<Box<dyn Matcher<T>>>) -> impl Matcher<T> {
+    AllOf { matchers }
+}
+
+/// Builds the "any of" combinator used by the [`any!`] macro. Prefer `any!`
+/// over calling this directly so each operand can be a distinct matcher
+/// type.
+pub fn any<T: ?Sized + 'static>(matchers: Vec<Box<dyn Matcher<T>>>) -> impl Matcher<T> {
+    AnyOf { matchers }
+}
+
+/// Builds the negation used by the [`not!`] macro.
+pub fn not<T: ?Sized + 'static>(matcher: impl Matcher<T> + 'static) -> impl Matcher<T> {
+    Not { matcher: Box::new(matcher) }
+}
+
+struct AllOf<T: ?Sized> {
+    matchers: Vec<Box<dyn Matcher<T>>>,
+}
+
+impl<T: ?Sized> Matcher<T> for AllOf<T> {
+    fn matches(&self, actual: &T) -> MatchResult {
+        for matcher in &self.matchers {
+            let result = matcher.matches(actual);
+            if !result.is_match() {
+                return result;
+            }
+        }
+        MatchResult::Matches
+    }
+
+    fn describe(&self) -> String {
+        self.matchers.iter().map(|m| m.describe()).collect::<Vec<_>>().join(", and ")
+    }
+}
+
+struct AnyOf<T: ?Sized> {
+    matchers: Vec<Box<dyn Matcher<T>>>,
+}
+
+impl<T: ?Sized> Matcher<T> for AnyOf<T> {
+    fn matches(&self, actual: &T) -> MatchResult {
+        for matcher in &self.matchers {
+            if matcher.matches(actual).is_match() {
+                return MatchResult::Matches;
+            }
+        }
+        MatchResult::DoesNotMatch(String::new())
+    }
+
+    fn describe(&self) -> String {
+        self.matchers.iter().map(|m| m.describe()).collect::<Vec<_>>().join(", or ")
+    }
+}
+
+struct Not<T: ?Sized> {
+    matcher: Box<dyn Matcher<T>>,
+}
+
+impl<T: ?Sized> Matcher<T> for Not<T> {
+    fn matches(&self, actual: &T) -> MatchResult {
+        if self.matcher.matches(actual).is_match() {
+            MatchResult::DoesNotMatch(String::new())
+        } else {
+            MatchResult::Matches
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("not ({})", self.matcher.describe())
+    }
+}
+
+/// A pointer-like value `pointee` can look through to find the value to
+/// match: a reference, a `Box`, or an `Option` (treating `None` as an
+/// empty pointer, the way GoogleTest's `Pointee` treats a null pointer).
+pub trait Pointer {
+    type Pointee: ?Sized;
+
+    /// Returns the pointee, or `None` if this pointer is "null" (i.e. an
+    /// `Option::None`).
+    fn as_pointee(&self) -> Option<&Self::Pointee>;
+}
+
+impl<T: ?Sized> Pointer for &T {
+    type Pointee = T;
+
+    fn as_pointee(&self) -> Option<&T> {
+        Some(self)
+    }
+}
+
+impl<T> Pointer for Box<T> {
+    type Pointee = T;
+
+    fn as_pointee(&self) -> Option<&T> {
+        Some(self)
+    }
+}
+
+impl<T> Pointer for Option<T> {
+    type Pointee = T;
+
+    fn as_pointee(&self) -> Option<&T> {
+        self.as_ref()
+    }
+}
+
+struct PointeeMatcher<P: Pointer + ?Sized> {
+    inner: Box<dyn Matcher<P::Pointee>>,
+}
+
+impl<P: Pointer + ?Sized> Matcher<P> for PointeeMatcher<P> {
+    fn matches(&self, actual: &P) -> MatchResult {
+        match actual.as_pointee() {
+            Some(pointee) => self.inner.matches(pointee),
+            None => MatchResult::DoesNotMatch("which points to nothing".to_string()),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("points to a value that {}", self.inner.describe())
+    }
+}
+
+/// Matches a pointer-like value (`&T`, `Box<T>`, `Option<T>`) whose pointee
+/// satisfies `inner`, dereferencing (or unwrapping `Some`) before matching.
+/// Equivalent to GoogleTest's `Pointee`.
+pub fn pointee<P: Pointer + 'static>(inner: impl Matcher<P::Pointee> + 'static) -> impl Matcher<P> {
+    PointeeMatcher { inner: Box::new(inner) }
+}
+
+/// Matches a value whose `Debug` rendering contains the given pattern
+/// string, in the spirit of GoogleTest's `matches_pattern!`. This starter
+/// implementation checks substring containment against the `Debug` output;
+/// callers wanting true structural field matchers should compose
+/// `all!`/`eq`/`pointee` directly.
+pub fn matches_pattern_str<T: Debug>(pattern: impl Into<String>) -> impl Matcher<T> {
+    let pattern = pattern.into();
+    let description = format!("has debug format matching {pattern:?}");
+    FnMatcher {
+        description,
+        matches: Box::new(move |actual: &T| format!("{actual:?}").contains(&pattern)),
+    }
+}
+
+/// Matches a value whose `Debug` rendering contains the given pattern
+/// string. See [`matches_pattern_str`].
+#[macro_export]
+macro_rules! matches_pattern {
+    ($pattern:expr) => {
+        $crate::matchers::matches_pattern_str($pattern)
+    };
+}
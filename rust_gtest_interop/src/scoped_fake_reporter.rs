@@ -0,0 +1,124 @@
+// Copyright 2024 The Chromium Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! A Rust binding for GoogleTest's own `gtest-spi.h` self-testing
+//! machinery, used to assert that an `expect_*!` macro *fails* without that
+//! failure propagating to the real test result (and thus to the bots).
+
+use std::ffi::c_void;
+
+/// While alive, intercepts nonfatal failures reported on the current thread
+/// (via [`crate::ffi::add_failure_at`] and thus every `expect_*!` macro)
+/// instead of letting them reach the real, currently-running
+/// `::testing::Test`. Dropping it restores the real reporter.
+///
+/// This is a thin wrapper around `::testing::ScopedFakeTestPartResultReporter`
+/// and `::testing::TestPartResultArray`; prefer the [`crate::expect_nonfatal_failure!`]
+/// macro over using this type directly.
+pub struct ScopedFakeTestPartResultReporter {
+    handle: *mut c_void,
+}
+
+impl ScopedFakeTestPartResultReporter {
+    /// Installs the fake reporter for the current thread.
+    pub fn new() -> Self {
+        // SAFETY: the returned handle is owned by this value and released
+        // exactly once, in `Drop`.
+        let handle = unsafe { crate::ffi::rust_gtest_scoped_fake_reporter_new() };
+        ScopedFakeTestPartResultReporter { handle }
+    }
+
+    /// Returns how many nonfatal failures have been intercepted so far.
+    pub fn failure_count(&self) -> usize {
+        // SAFETY: `self.handle` is valid for the lifetime of `self`.
+        unsafe { crate::ffi::rust_gtest_scoped_fake_reporter_failure_count(self.handle) }
+    }
+
+    /// Returns the message of the intercepted failure at `index`.
+    ///
+    /// # Panics
+    /// Panics if `index >= self.failure_count()`.
+    pub fn failure_message(&self, index: usize) -> String {
+        let count = self.failure_count();
+        assert!(index < count, "failure index {index} out of range ({count} failures intercepted)");
+        // First ask for the length with a zero-sized buffer, then fetch the
+        // bytes into a buffer of the right size.
+        // SAFETY: a null pointer with `out_len == 0` is valid for the C++
+        // side, which only probes the length in that case.
+        let len = unsafe {
+            crate::ffi::rust_gtest_scoped_fake_reporter_failure_message(
+                self.handle,
+                index,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        let mut buf = vec![0u8; len];
+        // SAFETY: `buf` is valid for `len` bytes for the duration of the call.
+        let written = unsafe {
+            crate::ffi::rust_gtest_scoped_fake_reporter_failure_message(
+                self.handle,
+                index,
+                buf.as_mut_ptr(),
+                buf.len(),
+            )
+        };
+        assert_eq!(written, len, "failure message length changed between calls");
+        String::from_utf8(buf).expect("gtest failure messages are UTF-8")
+    }
+}
+
+impl Default for ScopedFakeTestPartResultReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ScopedFakeTestPartResultReporter {
+    fn drop(&mut self) {
+        // SAFETY: `self.handle` was created by `new` and hasn't been freed yet.
+        unsafe { crate::ffi::rust_gtest_scoped_fake_reporter_delete(self.handle) };
+    }
+}
+
+/// Runs `$stmt`, asserting that it records exactly one nonfatal gtest
+/// failure whose message contains `$substring`, and that the failure does
+/// not propagate to the real test result. Mirrors GoogleTest's C++
+/// `EXPECT_NONFATAL_FAILURE(stmt, substring)`.
+#[macro_export]
+macro_rules! expect_nonfatal_failure {
+    ($stmt:stmt, $substring:expr) => {{
+        let reporter = $crate::scoped_fake_reporter::ScopedFakeTestPartResultReporter::new();
+        $stmt
+        let count = reporter.failure_count();
+        let message = if count == 1 { Some(reporter.failure_message(0)) } else { None };
+        // Restore the real reporter before reporting below, so that these
+        // failures reach the running test instead of being intercepted too.
+        drop(reporter);
+        if count != 1 {
+            $crate::ffi::add_failure_at(
+                file!(),
+                line!(),
+                &format!(
+                    "Expected `{}` to record exactly 1 nonfatal failure, but it recorded {}",
+                    stringify!($stmt),
+                    count,
+                ),
+            );
+        } else if let Some(message) = message {
+            if !message.contains($substring) {
+                $crate::ffi::add_failure_at(
+                    file!(),
+                    line!(),
+                    &format!(
+                        "Expected failure from `{}` to contain {:?}, but its message was:\n{}",
+                        stringify!($stmt),
+                        $substring,
+                        message,
+                    ),
+                );
+            }
+        }
+    }};
+}
@@ -0,0 +1,188 @@
+// Copyright 2024 The Chromium Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Inline snapshot assertions: [`expect_snapshot!`] compares a value's
+//! `Debug` rendering against a string literal written at the call site, and
+//! can rewrite that literal in place when `UPDATE_GTEST_EXPECT=1` is set in
+//! the environment, the same "bless the output" workflow Chromium already
+//! has for other languages.
+//!
+//! Updates are collected rather than applied immediately, since applying
+//! one immediately would shift the line/column numbers of every other
+//! pending update in the same file. They are flushed once, at process
+//! exit, via `atexit`.
+//!
+//! The pending-update store is a process-global `Mutex`, not a
+//! `thread_local!`: by the time libc runs `atexit` callbacks, the main
+//! thread's thread-locals have already been torn down, so touching one
+//! from an `atexit` callback panics (and, since the callback is `extern
+//! "C"` and can't unwind, aborts the process). A plain `static` survives
+//! past that teardown.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs;
+use std::sync::Mutex;
+
+/// The environment variable that puts `expect_snapshot!` into "bless the
+/// output" mode, rewriting mismatching literals in place instead of (only)
+/// reporting a failure.
+pub const UPDATE_ENV_VAR: &str = "UPDATE_GTEST_EXPECT";
+
+struct PendingUpdate {
+    line: u32,
+    column: u32,
+    new_literal: String,
+}
+
+// Keyed by `file!()`, since that's the unit we rewrite at flush time.
+type PendingUpdatesByFile = HashMap<&'static str, Vec<PendingUpdate>>;
+static PENDING_UPDATES: Mutex<Option<PendingUpdatesByFile>> = Mutex::new(None);
+// Spans already blessed this run, so a test that runs the same
+// `expect_snapshot!` call site twice (e.g. via a loop, or parameterized
+// test cases sharing one body) doesn't splice the same literal twice and
+// corrupt the file.
+type BlessedSpan = (&'static str, u32, u32);
+static BLESSED_SPANS: Mutex<Option<std::collections::HashSet<BlessedSpan>>> = Mutex::new(None);
+
+extern "C" {
+    fn atexit(cb: extern "C" fn()) -> i32;
+}
+
+static REGISTER_FLUSH: std::sync::Once = std::sync::Once::new();
+
+fn update_mode_enabled() -> bool {
+    std::env::var(UPDATE_ENV_VAR).map(|v| v == "1").unwrap_or(false)
+}
+
+extern "C" fn flush_pending_updates() {
+    let pending = PENDING_UPDATES.lock().unwrap().take().unwrap_or_default();
+    for (file, updates) in pending {
+        if let Err(e) = rewrite_file(file, updates) {
+            eprintln!("expect_snapshot!: failed to update {file}: {e}");
+        }
+    }
+}
+
+fn rewrite_file(path: &str, mut updates: Vec<PendingUpdate>) -> std::io::Result<()> {
+    let source = fs::read_to_string(path)?;
+    let mut lines: Vec<String> = source.split_inclusive('\n').map(str::to_owned).collect();
+
+    // Apply later lines first, and within a line, later columns first, so
+    // that splicing one literal never moves the offset of another pending
+    // splice in the same file.
+    updates.sort_by_key(|u| std::cmp::Reverse((u.line, u.column)));
+
+    for update in updates {
+        let line_index = (update.line - 1) as usize;
+        let Some(line) = lines.get_mut(line_index) else { continue };
+        *line = splice_literal(line, update.column as usize, &update.new_literal);
+    }
+
+    fs::write(path, lines.concat())
+}
+
+/// Replaces the `@"..."` string literal that begins at or after `column`
+/// (1-based, as reported by `column!()`) in `line` with `new_literal` (a
+/// fully quoted-and-escaped Rust string literal, as produced by
+/// `format!("{:?}", s)`).
+fn splice_literal(line: &str, column: usize, new_literal: &str) -> String {
+    let bytes = line.as_bytes();
+    let start_hint = column.saturating_sub(1).min(bytes.len());
+    let Some(rel_at) = line[start_hint..].find('@') else { return line.to_owned() };
+    let at = start_hint + rel_at;
+    let Some(rel_quote_start) = line[at..].find('"') else { return line.to_owned() };
+    let quote_start = at + rel_quote_start;
+
+    // Scan for the matching unescaped closing quote.
+    let mut i = quote_start + 1;
+    let mut escaped = false;
+    let quote_end = loop {
+        let Some(c) = bytes.get(i) else { return line.to_owned() };
+        if escaped {
+            escaped = false;
+        } else if *c == b'\\' {
+            escaped = true;
+        } else if *c == b'"' {
+            break i;
+        }
+        i += 1;
+    };
+
+    format!("{}{}{}", &line[..quote_start], new_literal, &line[quote_end + 1..])
+}
+
+/// Checks `actual`'s `Debug` rendering against `expected`, reporting a
+/// nonfatal gtest failure on mismatch (with a line diff), and scheduling a
+/// source rewrite if [`UPDATE_ENV_VAR`] is set to `1`. Not meant to be called
+/// directly; see [`crate::expect_snapshot!`].
+#[doc(hidden)]
+pub fn check_snapshot(
+    actual: &impl Debug,
+    expected: &str,
+    file: &'static str,
+    line: u32,
+    column: u32,
+) {
+    let actual_rendered = format!("{actual:?}");
+    if actual_rendered == expected {
+        return;
+    }
+
+    if update_mode_enabled() {
+        let already_blessed =
+            !BLESSED_SPANS.lock().unwrap().get_or_insert_with(Default::default).insert((
+                file, line, column,
+            ));
+        if !already_blessed {
+            REGISTER_FLUSH.call_once(|| unsafe {
+                atexit(flush_pending_updates);
+            });
+            PENDING_UPDATES
+                .lock()
+                .unwrap()
+                .get_or_insert_with(Default::default)
+                .entry(file)
+                .or_default()
+                .push(PendingUpdate { line, column, new_literal: format!("{actual_rendered:?}") });
+        }
+    }
+
+    crate::ffi::add_failure_at(file, line, &format!("Snapshot mismatch:\n{}", line_diff(expected, &actual_rendered)));
+}
+
+/// A minimal line-level diff between `expected` and `actual`, prefixing
+/// unchanged lines with `" "`, removed lines with `"-"`, and added lines
+/// with `"+"` -- enough context to see what changed without pulling in a
+/// diffing crate for this one use.
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    let mut out = String::new();
+    for i in 0..expected_lines.len().max(actual_lines.len()) {
+        match (expected_lines.get(i), actual_lines.get(i)) {
+            (Some(e), Some(a)) if e == a => out.push_str(&format!(" {e}\n")),
+            (Some(e), Some(a)) => {
+                out.push_str(&format!("-{e}\n"));
+                out.push_str(&format!("+{a}\n"));
+            }
+            (Some(e), None) => out.push_str(&format!("-{e}\n")),
+            (None, Some(a)) => out.push_str(&format!("+{a}\n")),
+            (None, None) => unreachable!(),
+        }
+    }
+    out
+}
+
+/// Asserts that `$actual`'s `Debug` rendering equals the inline string
+/// literal `@"..."`, reporting a nonfatal gtest failure with a line diff on
+/// mismatch. When the `UPDATE_GTEST_EXPECT=1` environment variable is set,
+/// a mismatch is still reported, and also schedules this literal to be
+/// rewritten in place with the actual output once the process exits.
+#[macro_export]
+macro_rules! expect_snapshot {
+    ($actual:expr, @$expected:literal) => {
+        $crate::snapshot::check_snapshot(&$actual, $expected, file!(), line!(), column!())
+    };
+}